@@ -2,35 +2,58 @@ use std::collections::HashMap;
 use std::error::Error;
 
 use base64::Engine;
-use base64::prelude::BASE64_STANDARD;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use jwt::algorithm::openssl::PKeyWithDigest;
 use jwt::SigningAlgorithm;
-use openssl::hash::MessageDigest;
-use openssl::pkey::PKey;
+use openssl::ecdsa::EcdsaSig;
+use openssl::pkcs12::Pkcs12;
+use openssl::pkey::{PKey, Private};
+use openssl::rsa::Padding;
+use openssl::sign::{RsaPssSaltlen, Signer};
 
 use token::Header;
 
 use crate::token::{AccessTokenResponse, aud, Payload};
 
+pub use crate::token::{Algorithm, Thumbprint};
+
 mod token;
+mod manager;
+mod verifier;
 
-pub async fn acquire_token(tenant_id: String, client_id: String, scope: String, private_key_pem: &Vec<u8>, public_key_pem: &Vec<u8>) -> Result<AccessTokenResponse, Box<dyn Error>> {
-    let algorithm = PKeyWithDigest {
-        digest: MessageDigest::sha256(),
-        key: PKey::private_key_from_pem(&private_key_pem)?,
-    };
+pub use manager::TokenManager;
+pub use verifier::{TokenVerifier, ValidatedClaims};
 
-    let header = Header::new(&public_key_pem)?;
+pub async fn acquire_token(tenant_id: String, client_id: String, scope: String, algorithm: Algorithm, thumbprint: Thumbprint, private_key_pem: &[u8], public_key_pem: &[u8]) -> Result<AccessTokenResponse, Box<dyn Error>> {
+    let pkey = PKey::private_key_from_pem(private_key_pem)?;
+    acquire_token_with_key(tenant_id, client_id, scope, algorithm, thumbprint, pkey, public_key_pem).await
+}
+
+/// Same as [`acquire_token`], but extracts the signing key and certificate
+/// from a password-protected PKCS#12 (`.pfx`) bundle instead of separate PEM
+/// files, matching how Azure app-registration certificates are distributed.
+pub async fn acquire_token_pkcs12(tenant_id: String, client_id: String, scope: String, algorithm: Algorithm, thumbprint: Thumbprint, pfx: &[u8], password: &str) -> Result<AccessTokenResponse, Box<dyn Error>> {
+    let parsed = Pkcs12::from_der(pfx)?.parse2(password)?;
+    let cert = parsed.cert.ok_or("PKCS#12 bundle is missing a certificate")?;
+    let pkey = parsed.pkey.ok_or("PKCS#12 bundle is missing a private key")?;
+    let public_key_pem = cert.to_pem()?;
+    acquire_token_with_key(tenant_id, client_id, scope, algorithm, thumbprint, pkey, &public_key_pem).await
+}
+
+async fn acquire_token_with_key(tenant_id: String, client_id: String, scope: String, algorithm: Algorithm, thumbprint: Thumbprint, pkey: PKey<Private>, public_key_pem: &[u8]) -> Result<AccessTokenResponse, Box<dyn Error>> {
+    algorithm.validate_key(&pkey)?;
+
+    let header = Header::new(public_key_pem, algorithm, thumbprint)?;
     let payload = Payload::new(tenant_id.to_owned(), client_id.to_string());
     let header_json = serde_json::json!(header);
     let payload_json = serde_json::json!(payload);
 
-    let header_base64 = BASE64_STANDARD.encode(header_json.to_string());
-    let payload_base64 = BASE64_STANDARD.encode(payload_json.to_string());
-    let result = algorithm.sign(&header_base64, &payload_base64).unwrap();
+    let header_base64 = URL_SAFE_NO_PAD.encode(header_json.to_string());
+    let payload_base64 = URL_SAFE_NO_PAD.encode(payload_json.to_string());
+    let result = sign_assertion(algorithm, &pkey, &header_base64, &payload_base64)?;
     let client_assertion = format!("{}.{}.{}", header_base64, payload_base64, result);
 
-    let client = reqwest::Client::new();
+    let client = build_client()?;
     let mut params = HashMap::new();
     params.insert("client_assertion_type", "urn:ietf:params:oauth:client-assertion-type:jwt-bearer");
     params.insert("grant_type", "client_credentials");
@@ -65,6 +88,66 @@ pub async fn acquire_token(tenant_id: String, client_id: String, scope: String,
     ret
 }
 
+/// Builds the `reqwest` client used for the token request, selecting the
+/// pure-Rust `rustls` TLS backend or the system `native-tls` backend
+/// depending on which of the `rustls-tls` / `native-tls` cargo features is
+/// enabled, so the same flow works in musl/distroless and OpenSSL-backed
+/// deployments alike.
+pub(crate) fn build_client() -> Result<reqwest::Client, Box<dyn Error>> {
+    #[cfg(feature = "rustls-tls")]
+    let builder = reqwest::Client::builder().use_rustls_tls();
+
+    #[cfg(all(feature = "native-tls", not(feature = "rustls-tls")))]
+    let builder = reqwest::Client::builder().use_native_tls();
+
+    #[cfg(not(any(feature = "rustls-tls", feature = "native-tls")))]
+    let builder = reqwest::Client::builder();
+
+    Ok(builder.build()?)
+}
+
+/// Signs `header_base64.payload_base64` with `pkey` using the padding mode
+/// `algorithm` calls for (RSA-PSS vs PKCS#1 v1.5, or ECDSA), returning the
+/// base64-encoded signature.
+fn sign_assertion(algorithm: Algorithm, pkey: &PKey<Private>, header_base64: &str, payload_base64: &str) -> Result<String, Box<dyn Error>> {
+    if algorithm.uses_pss() {
+        let signing_input = format!("{}.{}", header_base64, payload_base64);
+        let mut signer = Signer::new(algorithm.message_digest(), pkey)?;
+        signer.set_rsa_padding(Padding::PKCS1_PSS)?;
+        signer.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+        signer.update(signing_input.as_bytes())?;
+        Ok(URL_SAFE_NO_PAD.encode(signer.sign_to_vec()?))
+    } else if algorithm.is_ecdsa() {
+        let signing_input = format!("{}.{}", header_base64, payload_base64);
+        let mut signer = Signer::new(algorithm.message_digest(), pkey)?;
+        signer.update(signing_input.as_bytes())?;
+        Ok(URL_SAFE_NO_PAD.encode(der_to_raw_ecdsa_signature(&signer.sign_to_vec()?, algorithm.ec_coordinate_len())?))
+    } else {
+        let signing_algorithm = PKeyWithDigest {
+            digest: algorithm.message_digest(),
+            key: pkey.clone(),
+        };
+        Ok(signing_algorithm.sign(header_base64, payload_base64)?)
+    }
+}
+
+/// Converts OpenSSL's DER-encoded `ECDSA-Sig-Value` (a SEQUENCE of two
+/// INTEGERs) into the fixed-width big-endian `r‖s` concatenation RFC 7518
+/// §3.4 requires for JWS ES256/ES384 signatures.
+fn der_to_raw_ecdsa_signature(der_signature: &[u8], coordinate_len: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    let sig = EcdsaSig::from_der(der_signature)?;
+    let r = sig.r().to_vec();
+    let s = sig.s().to_vec();
+    if r.len() > coordinate_len || s.len() > coordinate_len {
+        return Err("ECDSA signature component longer than the curve's coordinate size".into());
+    }
+
+    let mut raw = vec![0u8; coordinate_len * 2];
+    raw[coordinate_len - r.len()..coordinate_len].copy_from_slice(&r);
+    raw[2 * coordinate_len - s.len()..].copy_from_slice(&s);
+    Ok(raw)
+}
+
 #[cfg(test)]
 mod tests {
     use tokio;
@@ -81,7 +164,27 @@ mod tests {
         let public_key_pem = include_bytes!("../keys/public_key.pem").to_vec(); // Update with the correct path to your public key
 
         // Call the acquire_token function
-        let token_response = acquire_token(tenant_id, client_id, scope, &private_key_pem, &public_key_pem).await?;
+        let token_response = acquire_token(tenant_id, client_id, scope, Algorithm::Rs256, Thumbprint::default(), &private_key_pem, &public_key_pem).await?;
+
+        // Validate the response
+        assert_eq!(token_response.token_type, "Bearer");
+        assert!(token_response.expires_in > 0);
+        assert!(token_response.access_token.len() > 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_acquire_token_pkcs12() -> Result<(), Box<dyn Error>> {
+        // Setup test data
+        let tenant_id = "72f988bf-86f1-41af-91ab-2d7cd011db47".to_string();
+        let client_id = "064b969a-ed15-42fa-9044-f08081163a67".to_string();
+        let scope = "https://graph.microsoft.com/.default".to_string();
+        let pfx = include_bytes!("../keys/cert.pfx"); // Update with the correct path to your PKCS#12 bundle
+        let password = "changeit"; // Update with the correct PKCS#12 password
+
+        // Call the acquire_token_pkcs12 function
+        let token_response = acquire_token_pkcs12(tenant_id, client_id, scope, Algorithm::Rs256, Thumbprint::default(), pfx, password).await?;
 
         // Validate the response
         assert_eq!(token_response.token_type, "Bearer");
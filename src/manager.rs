@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+use crate::{acquire_token, Algorithm, Thumbprint};
+use crate::token::AccessTokenResponse;
+
+const DEFAULT_SKEW: Duration = Duration::from_secs(60);
+
+type FetchResult = Result<AccessTokenResponse, Box<dyn Error>>;
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Seam over [`acquire_token`] so tests can exercise the cache/expiry logic
+/// below without hitting `login.microsoftonline.com`.
+trait TokenFetcher: Send + Sync {
+    fn fetch(&self, scope: String) -> BoxFuture<'_, FetchResult>;
+}
+
+struct LiveFetcher {
+    tenant_id: String,
+    client_id: String,
+    algorithm: Algorithm,
+    thumbprint: Thumbprint,
+    private_key_pem: Vec<u8>,
+    public_key_pem: Vec<u8>,
+}
+
+impl TokenFetcher for LiveFetcher {
+    fn fetch(&self, scope: String) -> BoxFuture<'_, FetchResult> {
+        Box::pin(acquire_token(
+            self.tenant_id.clone(),
+            self.client_id.clone(),
+            scope,
+            self.algorithm,
+            self.thumbprint,
+            &self.private_key_pem,
+            &self.public_key_pem,
+        ))
+    }
+}
+
+struct CachedToken {
+    response: AccessTokenResponse,
+    expires_at: Instant,
+}
+
+/// Per-scope slot: its own mutex is held across the fetch so concurrent
+/// callers for the *same* scope coalesce onto one fetch, while callers for
+/// other scopes aren't blocked behind it.
+type ScopeSlot = Arc<Mutex<Option<CachedToken>>>;
+
+/// Wraps the tenant/client/key material needed to call [`acquire_token`] and
+/// caches the resulting [`AccessTokenResponse`] per scope, re-acquiring only
+/// once the cached token is within `skew` of its `expires_in` expiry.
+pub struct TokenManager {
+    fetcher: Box<dyn TokenFetcher>,
+    skew: Duration,
+    cache: Mutex<HashMap<String, ScopeSlot>>,
+}
+
+impl TokenManager {
+    pub fn new(tenant_id: String, client_id: String, private_key_pem: Vec<u8>, public_key_pem: Vec<u8>) -> Self {
+        Self::with_skew(tenant_id, client_id, private_key_pem, public_key_pem, DEFAULT_SKEW)
+    }
+
+    pub fn with_skew(tenant_id: String, client_id: String, private_key_pem: Vec<u8>, public_key_pem: Vec<u8>, skew: Duration) -> Self {
+        Self::with_options(tenant_id, client_id, private_key_pem, public_key_pem, Algorithm::default(), Thumbprint::default(), skew)
+    }
+
+    pub fn with_options(tenant_id: String, client_id: String, private_key_pem: Vec<u8>, public_key_pem: Vec<u8>, algorithm: Algorithm, thumbprint: Thumbprint, skew: Duration) -> Self {
+        let fetcher = LiveFetcher {
+            tenant_id,
+            client_id,
+            algorithm,
+            thumbprint,
+            private_key_pem,
+            public_key_pem,
+        };
+        Self::from_fetcher(Box::new(fetcher), skew)
+    }
+
+    fn from_fetcher(fetcher: Box<dyn TokenFetcher>, skew: Duration) -> Self {
+        Self {
+            fetcher,
+            skew,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns a cached `access_token` for `scope` if one is still valid,
+    /// otherwise acquires a new one and caches it. Only callers for the same
+    /// scope coalesce onto the in-flight fetch; other scopes aren't blocked.
+    pub async fn access_token(&self, scope: String) -> Result<String, Box<dyn Error>> {
+        let slot = {
+            let mut cache = self.cache.lock().await;
+            cache.entry(scope.clone()).or_insert_with(|| Arc::new(Mutex::new(None))).clone()
+        };
+
+        let mut cached = slot.lock().await;
+        if let Some(cached) = cached.as_ref() {
+            if Instant::now() < cached.expires_at {
+                return Ok(cached.response.access_token.clone());
+            }
+        }
+
+        let response = self.fetcher.fetch(scope).await?;
+
+        let expires_at = Instant::now() + Duration::from_secs(response.expires_in).checked_sub(self.skew).unwrap_or(Duration::ZERO);
+        let access_token = response.access_token.clone();
+        *cached = Some(CachedToken { response, expires_at });
+
+        Ok(access_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    struct CountingFetcher {
+        calls: Arc<AtomicUsize>,
+        expires_in: u64,
+    }
+
+    impl TokenFetcher for CountingFetcher {
+        fn fetch(&self, _scope: String) -> BoxFuture<'_, FetchResult> {
+            let call_count = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let expires_in = self.expires_in;
+            Box::pin(async move {
+                Ok(AccessTokenResponse {
+                    token_type: "Bearer".to_string(),
+                    expires_in,
+                    ext_expires_in: expires_in,
+                    access_token: format!("token-{}", call_count),
+                    id_token: None,
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_access_token_reuses_cached_value() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let fetcher = CountingFetcher { calls: calls.clone(), expires_in: 3600 };
+        let manager = TokenManager::from_fetcher(Box::new(fetcher), DEFAULT_SKEW);
+
+        let first = manager.access_token("scope-a".to_string()).await.unwrap();
+        let second = manager.access_token("scope-a".to_string()).await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_access_token_refreshes_past_skew() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let fetcher = CountingFetcher { calls: calls.clone(), expires_in: 30 };
+        let manager = TokenManager::from_fetcher(Box::new(fetcher), Duration::from_secs(60));
+
+        let first = manager.access_token("scope-a".to_string()).await.unwrap();
+        let second = manager.access_token("scope-a".to_string()).await.unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_access_token_caches_independently_per_scope() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let fetcher = CountingFetcher { calls: calls.clone(), expires_in: 3600 };
+        let manager = TokenManager::from_fetcher(Box::new(fetcher), DEFAULT_SKEW);
+
+        manager.access_token("scope-a".to_string()).await.unwrap();
+        manager.access_token("scope-b".to_string()).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -2,8 +2,11 @@ use std::error::Error;
 use std::time::SystemTime;
 
 use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::prelude::BASE64_STANDARD;
 use openssl::hash::{hash, MessageDigest};
+use openssl::nid::Nid;
+use openssl::pkey::{Id, PKey, Private};
 use openssl::x509::X509;
 use serde::{Deserialize, Serialize};
 
@@ -11,31 +14,145 @@ pub fn aud(tenant_id: String) -> String {
     format!("https://login.microsoftonline.com/{}/oauth2/v2.0/token", tenant_id)
 }
 
+/// JWS signing algorithm for the client assertion, selecting both the `alg`
+/// header value and the OpenSSL digest/padding used to produce the signature.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Algorithm {
+    #[default]
+    Rs256,
+    Rs384,
+    Rs512,
+    Ps256,
+    Ps384,
+    Ps512,
+    Es256,
+    Es384,
+}
+
+impl Algorithm {
+    pub fn jws_alg(&self) -> &'static str {
+        match self {
+            Algorithm::Rs256 => "RS256",
+            Algorithm::Rs384 => "RS384",
+            Algorithm::Rs512 => "RS512",
+            Algorithm::Ps256 => "PS256",
+            Algorithm::Ps384 => "PS384",
+            Algorithm::Ps512 => "PS512",
+            Algorithm::Es256 => "ES256",
+            Algorithm::Es384 => "ES384",
+        }
+    }
+
+    pub fn message_digest(&self) -> MessageDigest {
+        match self {
+            Algorithm::Rs256 | Algorithm::Ps256 | Algorithm::Es256 => MessageDigest::sha256(),
+            Algorithm::Rs384 | Algorithm::Ps384 | Algorithm::Es384 => MessageDigest::sha384(),
+            Algorithm::Rs512 | Algorithm::Ps512 => MessageDigest::sha512(),
+        }
+    }
+
+    /// Whether the signature uses RSA-PSS padding rather than PKCS#1 v1.5.
+    pub fn uses_pss(&self) -> bool {
+        matches!(self, Algorithm::Ps256 | Algorithm::Ps384 | Algorithm::Ps512)
+    }
+
+    /// Whether this is an ECDSA algorithm, which needs the DER `ECDSA-Sig-Value`
+    /// OpenSSL produces converted to the raw `r‖s` encoding RFC 7518 §3.4 requires.
+    pub fn is_ecdsa(&self) -> bool {
+        matches!(self, Algorithm::Es256 | Algorithm::Es384)
+    }
+
+    /// Byte length of each of `r` and `s` in the raw ECDSA JWS signature.
+    pub fn ec_coordinate_len(&self) -> usize {
+        match self {
+            Algorithm::Es256 => 32,
+            Algorithm::Es384 => 48,
+            _ => 0,
+        }
+    }
+
+    fn expected_curve(&self) -> Nid {
+        match self {
+            Algorithm::Es256 => Nid::X9_62_PRIME256V1,
+            Algorithm::Es384 => Nid::SECP384R1,
+            _ => unreachable!("expected_curve is only called for EC algorithms"),
+        }
+    }
+
+    /// Validates that `pkey` is the right type (and, for ECDSA, the right
+    /// curve) for this algorithm. RFC 7518 §3.4 ties ES256 to P-256 and
+    /// ES384 to P-384 specifically, so checking `Id::EC` alone isn't enough.
+    pub fn validate_key(&self, pkey: &PKey<Private>) -> Result<(), Box<dyn Error>> {
+        if self.is_ecdsa() {
+            if pkey.id() != Id::EC {
+                return Err(format!("algorithm {} requires an EC key", self.jws_alg()).into());
+            }
+            let curve = pkey.ec_key()?.group().curve_name().ok_or("EC key has no named curve")?;
+            let expected = self.expected_curve();
+            if curve != expected {
+                return Err(format!("algorithm {} requires curve {:?}, got {:?}", self.jws_alg(), expected, curve).into());
+            }
+        } else if pkey.id() != Id::RSA {
+            return Err(format!("algorithm {} requires an RSA key", self.jws_alg()).into());
+        }
+        Ok(())
+    }
+}
+
+/// Which certificate thumbprint header parameter(s) to emit on the JWS
+/// header: the legacy SHA-1 `x5t`, the modern SHA-256 `x5t#S256`, or both.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Thumbprint {
+    #[default]
+    Sha1,
+    Sha256,
+    Both,
+}
+
 #[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Header {
-    x5t: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    x5t: Option<String>,
     alg: String,
     x5c: Vec<String>,
+    #[serde(rename = "x5t#S256", skip_serializing_if = "Option::is_none")]
+    x5t_s256: Option<String>,
 }
 
 impl Header {
-    pub fn new(public_key_pem: &[u8]) -> Result<Self, Box<dyn Error>> {
+    pub fn new(public_key_pem: &[u8], algorithm: Algorithm, thumbprint: Thumbprint) -> Result<Self, Box<dyn Error>> {
         let cert_pem = Self::calc_pem(public_key_pem)?;
-        let x5t = Self::calc_x5t(cert_pem.clone())?;
+        let der = BASE64_STANDARD.decode(&cert_pem)?;
+
+        let x5t = match thumbprint {
+            Thumbprint::Sha1 | Thumbprint::Both => Some(Self::calc_x5t(&der)?),
+            Thumbprint::Sha256 => None,
+        };
+        let x5t_s256 = match thumbprint {
+            Thumbprint::Sha256 | Thumbprint::Both => Some(Self::calc_x5t_s256(&der)?),
+            Thumbprint::Sha1 => None,
+        };
 
         Ok(Self {
-            alg: String::from("RS256"),
+            alg: String::from(algorithm.jws_alg()),
             x5t,
             x5c: vec![cert_pem],
+            x5t_s256,
         })
     }
-    fn calc_x5t(public_key_pem: String) -> Result<String, Box<dyn Error>> {
-        let data = BASE64_STANDARD.decode(public_key_pem)?;
-        let hash = hash(MessageDigest::sha1(), &data)?;
-        let x5t = BASE64_STANDARD.encode(&hash);
-        // let x5t: String = hash.iter().map(|&x| format!("{:02X}", x)).collect();
-        Ok(x5t)
+
+    /// Legacy SHA-1 thumbprint (`x5t`) of the DER-encoded certificate.
+    fn calc_x5t(der_cert: &[u8]) -> Result<String, Box<dyn Error>> {
+        let hash = hash(MessageDigest::sha1(), der_cert)?;
+        Ok(URL_SAFE_NO_PAD.encode(hash))
+    }
+
+    /// SHA-256 thumbprint (`x5t#S256`) of the DER-encoded certificate, used
+    /// by Azure's Subject Name + Issuer authentication.
+    fn calc_x5t_s256(der_cert: &[u8]) -> Result<String, Box<dyn Error>> {
+        let hash = hash(MessageDigest::sha256(), der_cert)?;
+        Ok(URL_SAFE_NO_PAD.encode(hash))
     }
 
     fn calc_pem(public_key_pem: &[u8]) -> Result<String, Box<dyn Error>> {
@@ -79,6 +196,8 @@ pub struct AccessTokenResponse {
     pub expires_in: u64,
     pub ext_expires_in: u64,
     pub access_token: String,
+    #[serde(default)]
+    pub id_token: Option<String>,
 }
 
 
@@ -89,11 +208,32 @@ mod tests {
     #[tokio::test]
     async fn test_header_new() -> Result<(), Box<dyn Error>> {
         let public_key_pem = include_bytes!("../../keys/public_key.pem").to_vec(); // Update with the correct path to your public key
-        let header = Header::new(&public_key_pem)?;
+        let header = Header::new(&public_key_pem, Algorithm::Rs256, Thumbprint::Sha1)?;
 
         assert_eq!(header.alg, "RS256");
         assert_eq!(header.x5c.len(), 1);
-        assert!(header.x5t.len() > 0);
+        assert!(header.x5t.unwrap().len() > 0);
+        assert!(header.x5t_s256.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_header_new_both_thumbprints() -> Result<(), Box<dyn Error>> {
+        let public_key_pem = include_bytes!("../../keys/public_key.pem").to_vec(); // Update with the correct path to your public key
+        let header = Header::new(&public_key_pem, Algorithm::Rs256, Thumbprint::Both)?;
+
+        assert!(header.x5t.is_some());
+        assert!(header.x5t_s256.unwrap().len() > 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_header_new_sha256_thumbprint() -> Result<(), Box<dyn Error>> {
+        let public_key_pem = include_bytes!("../../keys/public_key.pem").to_vec(); // Update with the correct path to your public key
+        let header = Header::new(&public_key_pem, Algorithm::Rs256, Thumbprint::Sha256)?;
+
+        assert!(header.x5t.is_none());
+        assert!(header.x5t_s256.unwrap().len() > 0);
         Ok(())
     }
 
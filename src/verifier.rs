@@ -0,0 +1,272 @@
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::SystemTime;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use openssl::bn::BigNum;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey, Public};
+use openssl::rsa::Rsa;
+use openssl::sign::Verifier;
+use serde::Deserialize;
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Seam over the tenant's JWKS endpoint so tests can exercise signature and
+/// claim verification without hitting `login.microsoftonline.com`.
+trait JwksSource: Send + Sync {
+    fn fetch(&self) -> BoxFuture<'_, Result<Jwks, Box<dyn Error>>>;
+}
+
+struct HttpJwksSource {
+    client: reqwest::Client,
+    uri: String,
+}
+
+impl JwksSource for HttpJwksSource {
+    fn fetch(&self) -> BoxFuture<'_, Result<Jwks, Box<dyn Error>>> {
+        Box::pin(async move {
+            Ok(self.client.get(&self.uri).send().await?.json::<Jwks>().await?)
+        })
+    }
+}
+
+/// Verifies RS256-signed Azure AD tokens against a tenant's published JWKS,
+/// checking the signature and the `aud`/`iss`/`exp`/`nbf` claims. Opt-in:
+/// callers that don't need this can keep using [`crate::acquire_token`]'s
+/// response as-is.
+pub struct TokenVerifier {
+    tenant_id: String,
+    audience: String,
+    jwks_source: Box<dyn JwksSource>,
+}
+
+impl TokenVerifier {
+    pub fn new(tenant_id: String, audience: String) -> Result<Self, Box<dyn Error>> {
+        let uri = format!("https://login.microsoftonline.com/{}/discovery/v2.0/keys", tenant_id);
+        let jwks_source = HttpJwksSource { client: crate::build_client()?, uri };
+        Ok(Self::from_jwks_source(tenant_id, audience, Box::new(jwks_source)))
+    }
+
+    fn from_jwks_source(tenant_id: String, audience: String, jwks_source: Box<dyn JwksSource>) -> Self {
+        Self { tenant_id, audience, jwks_source }
+    }
+
+    fn issuer(&self) -> String {
+        format!("https://login.microsoftonline.com/{}/v2.0", self.tenant_id)
+    }
+
+    /// Verifies `token`'s RS256 signature against the tenant's JWKS and its
+    /// `aud`/`iss`/`exp`/`nbf` claims, returning the validated claims.
+    pub async fn verify(&self, token: &str) -> Result<ValidatedClaims, Box<dyn Error>> {
+        let mut segments = token.split('.');
+        let header_b64 = segments.next().ok_or("token is missing a header segment")?;
+        let payload_b64 = segments.next().ok_or("token is missing a payload segment")?;
+        let signature_b64 = segments.next().ok_or("token is missing a signature segment")?;
+        if segments.next().is_some() {
+            return Err("token has more than three segments".into());
+        }
+
+        let header: TokenHeader = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header_b64)?)?;
+        let claims: ValidatedClaims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload_b64)?)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature_b64)?;
+
+        let jwks = self.jwks_source.fetch().await?;
+        let jwk = jwks.keys.iter().find(|key| key.kid == header.kid)
+            .ok_or_else(|| format!("no JWKS key found for kid {}", header.kid))?;
+
+        if !verify_signature(jwk, header_b64, payload_b64, &signature)? {
+            return Err("token signature verification failed".into());
+        }
+
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+        verify_claims(&claims, &self.audience, &self.issuer(), now)?;
+
+        Ok(claims)
+    }
+}
+
+/// Verifies `header_b64.payload_b64`'s RS256 signature against `jwk`.
+fn verify_signature(jwk: &Jwk, header_b64: &str, payload_b64: &str, signature: &[u8]) -> Result<bool, Box<dyn Error>> {
+    let signing_input = format!("{}.{}", header_b64, payload_b64);
+    let public_key = jwk.to_rsa_public_key()?;
+    let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key)?;
+    verifier.update(signing_input.as_bytes())?;
+    Ok(verifier.verify(signature)?)
+}
+
+/// Checks `claims`' `aud`/`iss`/`exp`/`nbf` against the expected audience,
+/// issuer, and the current time.
+fn verify_claims(claims: &ValidatedClaims, audience: &str, issuer: &str, now: u64) -> Result<(), Box<dyn Error>> {
+    if claims.aud != audience {
+        return Err(format!("unexpected audience: {}", claims.aud).into());
+    }
+    if claims.iss != issuer {
+        return Err(format!("unexpected issuer: {}", claims.iss).into());
+    }
+    if now >= claims.exp {
+        return Err("token has expired".into());
+    }
+    if now < claims.nbf {
+        return Err("token is not yet valid".into());
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct TokenHeader {
+    kid: String,
+}
+
+#[derive(Deserialize)]
+struct Jwks {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Deserialize)]
+struct Jwk {
+    kid: String,
+    n: String,
+    e: String,
+}
+
+impl Jwk {
+    fn to_rsa_public_key(&self) -> Result<PKey<Public>, Box<dyn Error>> {
+        let n = BigNum::from_slice(&URL_SAFE_NO_PAD.decode(&self.n)?)?;
+        let e = BigNum::from_slice(&URL_SAFE_NO_PAD.decode(&self.e)?)?;
+        Ok(PKey::from_rsa(Rsa::from_public_components(n, e)?)?)
+    }
+}
+
+/// Claims of a token that has passed [`TokenVerifier::verify`].
+#[derive(Clone, Debug, Deserialize)]
+pub struct ValidatedClaims {
+    pub aud: String,
+    pub iss: String,
+    pub exp: u64,
+    pub nbf: u64,
+    #[serde(flatten)]
+    pub extra: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::pkey::Private;
+    use openssl::sign::Signer;
+
+    use super::*;
+
+    struct FakeJwksSource(Vec<Jwk>);
+
+    impl JwksSource for FakeJwksSource {
+        fn fetch(&self) -> BoxFuture<'_, Result<Jwks, Box<dyn Error>>> {
+            let keys = self.0.iter().map(|jwk| Jwk { kid: jwk.kid.clone(), n: jwk.n.clone(), e: jwk.e.clone() }).collect();
+            Box::pin(async move { Ok(Jwks { keys }) })
+        }
+    }
+
+    fn generate_key_and_jwk(kid: &str) -> (PKey<Private>, Jwk) {
+        let rsa = Rsa::generate(2048).unwrap();
+        let n = URL_SAFE_NO_PAD.encode(rsa.n().to_owned().unwrap().to_vec());
+        let e = URL_SAFE_NO_PAD.encode(rsa.e().to_owned().unwrap().to_vec());
+        (PKey::from_rsa(rsa).unwrap(), Jwk { kid: kid.to_string(), n, e })
+    }
+
+    fn sign_token(pkey: &PKey<Private>, header_json: &str, payload_json: &str) -> String {
+        let header_b64 = URL_SAFE_NO_PAD.encode(header_json);
+        let payload_b64 = URL_SAFE_NO_PAD.encode(payload_json);
+        let signing_input = format!("{}.{}", header_b64, payload_b64);
+
+        let mut signer = Signer::new(MessageDigest::sha256(), pkey).unwrap();
+        signer.update(signing_input.as_bytes()).unwrap();
+        let signature_b64 = URL_SAFE_NO_PAD.encode(signer.sign_to_vec().unwrap());
+
+        format!("{}.{}.{}", header_b64, payload_b64, signature_b64)
+    }
+
+    #[tokio::test]
+    async fn test_verify_accepts_a_validly_signed_token() {
+        let (pkey, jwk) = generate_key_and_jwk("test-kid");
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        let header = serde_json::json!({"alg": "RS256", "typ": "JWT", "kid": "test-kid"}).to_string();
+        let payload = serde_json::json!({
+            "aud": "api://test-audience",
+            "iss": "https://login.microsoftonline.com/test-tenant/v2.0",
+            "exp": now + 3600,
+            "nbf": now - 60,
+        }).to_string();
+        let token = sign_token(&pkey, &header, &payload);
+
+        let verifier = TokenVerifier::from_jwks_source(
+            "test-tenant".to_string(),
+            "api://test-audience".to_string(),
+            Box::new(FakeJwksSource(vec![jwk])),
+        );
+
+        let claims = verifier.verify(&token).await.unwrap();
+        assert_eq!(claims.aud, "api://test-audience");
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_a_tampered_payload() {
+        let (pkey, jwk) = generate_key_and_jwk("test-kid");
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        let header = serde_json::json!({"alg": "RS256", "typ": "JWT", "kid": "test-kid"}).to_string();
+        let payload = serde_json::json!({
+            "aud": "api://test-audience",
+            "iss": "https://login.microsoftonline.com/test-tenant/v2.0",
+            "exp": now + 3600,
+            "nbf": now - 60,
+        }).to_string();
+        let token = sign_token(&pkey, &header, &payload);
+        let tampered_payload = URL_SAFE_NO_PAD.encode(serde_json::json!({
+            "aud": "api://a-different-audience",
+            "iss": "https://login.microsoftonline.com/test-tenant/v2.0",
+            "exp": now + 3600,
+            "nbf": now - 60,
+        }).to_string());
+        let mut segments: Vec<&str> = token.split('.').collect();
+        segments[1] = &tampered_payload;
+        let tampered_token = segments.join(".");
+
+        let verifier = TokenVerifier::from_jwks_source(
+            "test-tenant".to_string(),
+            "api://test-audience".to_string(),
+            Box::new(FakeJwksSource(vec![jwk])),
+        );
+
+        assert!(verifier.verify(&tampered_token).await.is_err());
+    }
+
+    #[test]
+    fn test_verify_claims_rejects_wrong_audience() {
+        let claims = ValidatedClaims { aud: "aud-a".to_string(), iss: "iss".to_string(), exp: 200, nbf: 0, extra: serde_json::Value::Null };
+        assert!(verify_claims(&claims, "aud-b", "iss", 100).is_err());
+    }
+
+    #[test]
+    fn test_verify_claims_rejects_wrong_issuer() {
+        let claims = ValidatedClaims { aud: "aud".to_string(), iss: "iss-a".to_string(), exp: 200, nbf: 0, extra: serde_json::Value::Null };
+        assert!(verify_claims(&claims, "aud", "iss-b", 100).is_err());
+    }
+
+    #[test]
+    fn test_verify_claims_rejects_expired_token() {
+        let claims = ValidatedClaims { aud: "aud".to_string(), iss: "iss".to_string(), exp: 100, nbf: 0, extra: serde_json::Value::Null };
+        assert!(verify_claims(&claims, "aud", "iss", 200).is_err());
+    }
+
+    #[test]
+    fn test_verify_claims_rejects_not_yet_valid_token() {
+        let claims = ValidatedClaims { aud: "aud".to_string(), iss: "iss".to_string(), exp: 200, nbf: 150, extra: serde_json::Value::Null };
+        assert!(verify_claims(&claims, "aud", "iss", 100).is_err());
+    }
+
+    #[test]
+    fn test_verify_claims_accepts_valid_claims() {
+        let claims = ValidatedClaims { aud: "aud".to_string(), iss: "iss".to_string(), exp: 200, nbf: 50, extra: serde_json::Value::Null };
+        assert!(verify_claims(&claims, "aud", "iss", 100).is_ok());
+    }
+}